@@ -2,6 +2,7 @@ use std::env;
 use std::process;
 use std::str::FromStr;
 use std::fmt;
+use std::thread;
 
 use image;
 
@@ -24,7 +25,63 @@ struct Config
     outside_color: [u8; 3],
     inside_color: [u8; 3],
     second_color: [u8; 3],
-    filename: String
+    filename: String,
+    threads: usize,
+    colorspace: ColorSpace,
+    smooth: bool,
+    julia: Option<(f64, f64)>,
+    power: i32,
+    palette: Option<Vec<[u8; 3]>>,
+    samples: u32,
+    formula: Option<Expr>
+}
+
+#[derive(Clone)]
+enum Expr
+{
+    Z,
+    C,
+    Const(f64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Neg(Box<Expr>),
+    Pow(Box<Expr>, i32),
+    Call(ComplexFn, Box<Expr>)
+}
+
+#[derive(Clone, Copy)]
+enum ComplexFn
+{
+    Sin,
+    Cos,
+    Exp,
+    Conj
+}
+
+#[derive(Clone, Copy)]
+enum ColorSpace
+{
+    Rgb,
+    Lab,
+    Luv
+}
+
+impl FromStr for ColorSpace
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s
+        {
+            "rgb" => Ok(ColorSpace::Rgb),
+            "lab" => Ok(ColorSpace::Lab),
+            "luv" => Ok(ColorSpace::Luv),
+            _ => Err(format!("unknown colorspace: {s}"))
+        }
+    }
 }
 
 impl Config
@@ -48,6 +105,22 @@ impl Config
 
         let mut filename: String = "output.png".to_string();
 
+        let mut threads: usize = default_threads();
+
+        let mut colorspace: ColorSpace = ColorSpace::Rgb;
+
+        let mut smooth: bool = true;
+
+        let mut julia: Option<(f64, f64)> = None;
+        let mut power: i32 = 2;
+
+        let mut palette_file: Option<String> = None;
+        let mut palette_row: u32 = 0;
+
+        let mut samples: u32 = 1;
+
+        let mut formula: Option<Expr> = None;
+
         let mut args = args.skip(1);
         while let Some(arg) = args.next()
         {
@@ -64,15 +137,64 @@ impl Config
                 "--inside" => inside_color = parse_color(args.next())?,
                 "--second" => second_color = parse_color(args.next())?,
                 "-o" => filename = args.next().ok_or("no filename")?,
+                "-j" | "--threads" => threads = parse_arg(args.next())?,
+                "--colorspace" => colorspace = parse_arg(args.next())?,
+                "--smooth" => smooth = true,
+                "--no-smooth" => smooth = false,
+                "--julia" => julia = Some(parse_pair(args.next())?),
+                "-p" | "--power" => power = parse_arg(args.next())?,
+                "--palette" => palette_file = Some(args.next().ok_or("no filename")?),
+                "--palette-row" => palette_row = parse_arg(args.next())?,
+                "-s" | "--samples" => samples = parse_arg(args.next())?,
+                "--formula" => formula = Some(parse_formula(&args.next().ok_or("no formula")?)?),
                 _ => return Err(format!("unrecongnized argument: {arg}"))
             }
         }
 
+        if threads == 0
+        {
+            return Err("threads must be at least 1".to_string());
+        }
+
+        if samples == 0
+        {
+            return Err("samples must be at least 1".to_string());
+        }
+
+        let palette = palette_file.map(|file| load_palette_row(&file, palette_row)).transpose()?;
+
         Ok(Config{x, y, zoom, iterations, width, height, mult,
-            outside_color, inside_color, second_color, filename})
+            outside_color, inside_color, second_color, filename, threads, colorspace, smooth,
+            julia, power, palette, samples, formula})
     }
 }
 
+fn load_palette_row(path: &str, row: u32) -> Result<Vec<[u8; 3]>, String>
+{
+    let image = image::open(path).map_err(|err| format!("cant open palette {path}: {err}"))?.to_rgb8();
+
+    if row >= image.height()
+    {
+        return Err(format!("palette row {row} is out of bounds for height {}", image.height()));
+    }
+
+    let row_pixels = (0..image.width())
+        .map(|x|
+        {
+            let pixel = image.get_pixel(x, row);
+
+            [pixel[0], pixel[1], pixel[2]]
+        })
+        .collect();
+
+    Ok(row_pixels)
+}
+
+fn default_threads() -> usize
+{
+    thread::available_parallelism().map(|x| x.get()).unwrap_or(1)
+}
+
 fn help_message() -> !
 {
     eprintln!("usage: {} [args]\n",
@@ -89,6 +211,15 @@ fn help_message() -> !
     eprintln!("    --inside    image height (default 255,0,0)");
     eprintln!("    --second    image height (default 255,0,255)");
     eprintln!("    -o    output filename (default output.png)");
+    eprintln!("    -j, --threads    amount of worker threads (default number of cpus)");
+    eprintln!("    --colorspace    rgb, lab or luv, space the colors are blended in (default rgb)");
+    eprintln!("    --smooth, --no-smooth    toggle smooth continuous coloring (default smooth)");
+    eprintln!("    --julia    cre,cim fix c and render the julia set instead of mandelbrot");
+    eprintln!("    -p, --power    exponent in z^p+c (default 2)");
+    eprintln!("    --palette    image file whose row is sampled as a gradient lookup table");
+    eprintln!("    --palette-row    row of the palette image to sample (default 0)");
+    eprintln!("    -s, --samples    NxN supersamples averaged per output pixel (default 1)");
+    eprintln!("    --formula    custom z update expression over z and c, e.g. \"z*z*z + c\"");
     process::exit(1);
 }
 
@@ -112,27 +243,94 @@ fn main()
 }
 
 fn mandelbrot(config: &Config) -> Image
+{
+    let row_bytes = config.width as usize * 3;
+
+    let mut data = vec![0u8; row_bytes * config.height as usize];
+
+    let rows_per_band = (config.height as usize).div_ceil(config.threads);
+    let band_bytes = row_bytes * rows_per_band.max(1);
+
+    thread::scope(|scope|
+    {
+        for (band_index, band) in data.chunks_mut(band_bytes).enumerate()
+        {
+            let y_offset = band_index * rows_per_band;
+
+            scope.spawn(move ||
+            {
+                render_band(config, band, y_offset, row_bytes);
+            });
+        }
+    });
+
+    Image{data, width: config.width, height: config.height}
+}
+
+fn render_band(config: &Config, band: &mut [u8], y_offset: usize, row_bytes: usize)
 {
     let offset = config.zoom/2.0;
 
-    let mut data = Vec::new();
-    for y in 0..config.height
+    let step_x = config.zoom/config.width as f64;
+    let step_y = config.zoom/config.height as f64;
+
+    let rows = band.len() / row_bytes;
+
+    for row in 0..rows
     {
+        let y = y_offset + row;
+
         for x in 0..config.width
         {
-            let x = config.x-offset + config.zoom*(x as f64/config.width as f64);
-            let y = config.y-offset + config.zoom*(y as f64/config.height as f64 );
+            let px = config.x-offset + config.zoom*(x as f64/config.width as f64);
+            let py = config.y-offset + config.zoom*(y as f64/config.height as f64);
+
+            let pixel = if config.samples > 1
+            {
+                supersampled_pixel(config, px, py, step_x, step_y)
+            } else
+            {
+                mandel_pixel(config, px, py)
+            };
+
+            let i = row*row_bytes + x as usize*3;
+            band[i..i+3].copy_from_slice(&pixel);
+        }
+    }
+}
+
+fn supersampled_pixel(config: &Config, px: f64, py: f64, step_x: f64, step_y: f64) -> [u8; 3]
+{
+    let mut sum = [0.0; 3];
+
+    for sy in 0..config.samples
+    {
+        for sx in 0..config.samples
+        {
+            let sample_x = px + (sx as f64 + 0.5)/config.samples as f64*step_x;
+            let sample_y = py + (sy as f64 + 0.5)/config.samples as f64*step_y;
+
+            let pixel = mandel_pixel(config, sample_x, sample_y);
 
-            data.extend(mandel_pixel(config, x, y).iter().cloned());
+            for channel in 0..3
+            {
+                sum[channel] += pixel[channel] as f64;
+            }
         }
     }
 
-    Image{data, width: config.width, height: config.height}
+    let total = (config.samples*config.samples) as f64;
+
+    [
+        (sum[0]/total).round() as u8,
+        (sum[1]/total).round() as u8,
+        (sum[2]/total).round() as u8
+    ]
 }
 
 fn mandel_pixel(config: &Config, x: f64, y: f64) -> [u8; 3]
 {
-    let (inside, distance) = pixel_distance(config.iterations, x, y);
+    let (inside, distance) = pixel_distance(config, x, y);
 
     let fraction =
     {
@@ -146,32 +344,69 @@ fn mandel_pixel(config: &Config, x: f64, y: f64) -> [u8; 3]
         }
     };
 
-    let inside_color = lerp(config.inside_color, config.second_color, fraction);
+    if let Some(palette) = &config.palette
+    {
+        let position = if inside { fraction } else { distance };
+
+        return sample_palette(palette, position);
+    }
+
+    let inside_color = lerp(config.inside_color, config.second_color, fraction, config.colorspace);
 
     if inside
     {
         inside_color
     } else
     {
-        lerp(config.outside_color, inside_color, distance)
+        lerp(config.outside_color, inside_color, distance, config.colorspace)
     }
 }
 
-fn pixel_distance(iterations: u128, x: f64, y: f64) -> (bool, f64)
+fn sample_palette(row: &[[u8; 3]], position: f64) -> [u8; 3]
 {
-    let (mut z_r, mut z_i) = (0.0, 0.0);
+    let position = position.clamp(0.0, 1.0) * (row.len()-1) as f64;
+
+    let low = position.floor() as usize;
+    let high = (low+1).min(row.len()-1);
+
+    lerp_rgb(row[low], row[high], position - low as f64)
+}
+
+fn pixel_distance(config: &Config, x: f64, y: f64) -> (bool, f64)
+{
+    let (c_r, c_i) = config.julia.unwrap_or((x, y));
+    let (mut z_r, mut z_i) = if config.julia.is_some() { (x, y) } else { (0.0, 0.0) };
 
     let distance = |v0, v1| v0*v0+v1*v1;
 
-    for i in 0..iterations
+    for i in 0..config.iterations
     {
-        let temp_z = z_r*z_r + x - z_i*z_i;
-        z_i = 2.0*z_r*z_i + y;
-        z_r = temp_z;
+        let (next_r, next_i) = if let Some(formula) = &config.formula
+        {
+            eval_expr(formula, (z_r, z_i), (c_r, c_i))
+        } else
+        {
+            let (p_r, p_i) = complex_pow(z_r, z_i, config.power);
 
-        if distance(z_r, z_i) > 4.0
+            (p_r + c_r, p_i + c_i)
+        };
+
+        z_r = next_r;
+        z_i = next_i;
+
+        let magnitude = distance(z_r, z_i);
+        if magnitude > 256.0
         {
-            let fraction = (i as f64)/(iterations as f64);
+            let fraction = if config.smooth
+            {
+                let nu = (i as f64) + 1.0 - (0.5*magnitude.ln()/2.0_f64.ln()).ln()/2.0_f64.ln();
+
+                nu/(config.iterations as f64)
+            } else
+            {
+                (i as f64)/(config.iterations as f64)
+            };
+
             return (false, fraction);
         }
     }
@@ -179,9 +414,348 @@ fn pixel_distance(iterations: u128, x: f64, y: f64) -> (bool, f64)
     (true, distance(z_r, z_i))
 }
 
-fn lerp(c0: [u8; 3], c1: [u8; 3], amount: f64) -> [u8; 3]
+fn complex_pow(z_r: f64, z_i: f64, power: i32) -> (f64, f64)
+{
+    if power == 2
+    {
+        return (z_r*z_r - z_i*z_i, 2.0*z_r*z_i);
+    }
+
+    let r = (z_r*z_r + z_i*z_i).sqrt();
+    let theta = z_i.atan2(z_r);
+
+    let r_p = r.powi(power);
+    let theta_p = theta*power as f64;
+
+    (r_p*theta_p.cos(), r_p*theta_p.sin())
+}
+
+fn eval_expr(expr: &Expr, z: (f64, f64), c: (f64, f64)) -> (f64, f64)
+{
+    match expr
+    {
+        Expr::Z => z,
+        Expr::C => c,
+        Expr::Const(value) => (*value, 0.0),
+        Expr::Add(a, b) => complex_add(eval_expr(a, z, c), eval_expr(b, z, c)),
+        Expr::Sub(a, b) => complex_sub(eval_expr(a, z, c), eval_expr(b, z, c)),
+        Expr::Mul(a, b) => complex_mul(eval_expr(a, z, c), eval_expr(b, z, c)),
+        Expr::Div(a, b) => complex_div(eval_expr(a, z, c), eval_expr(b, z, c)),
+        Expr::Neg(a) =>
+        {
+            let (r, i) = eval_expr(a, z, c);
+
+            (-r, -i)
+        },
+        Expr::Pow(a, power) =>
+        {
+            let (r, i) = eval_expr(a, z, c);
+
+            complex_pow(r, i, *power)
+        },
+        Expr::Call(func, a) => eval_call(*func, eval_expr(a, z, c))
+    }
+}
+
+fn complex_add((ar, ai): (f64, f64), (br, bi): (f64, f64)) -> (f64, f64)
+{
+    (ar+br, ai+bi)
+}
+
+fn complex_sub((ar, ai): (f64, f64), (br, bi): (f64, f64)) -> (f64, f64)
+{
+    (ar-br, ai-bi)
+}
+
+fn complex_mul((ar, ai): (f64, f64), (br, bi): (f64, f64)) -> (f64, f64)
+{
+    (ar*br - ai*bi, ar*bi + ai*br)
+}
+
+fn complex_div((ar, ai): (f64, f64), (br, bi): (f64, f64)) -> (f64, f64)
+{
+    let denom = br*br + bi*bi;
+
+    ((ar*br + ai*bi)/denom, (ai*br - ar*bi)/denom)
+}
+
+fn eval_call(func: ComplexFn, (zr, zi): (f64, f64)) -> (f64, f64)
 {
-    let v_lerp = |n|
+    match func
+    {
+        ComplexFn::Sin => (zr.sin()*zi.cosh(), zr.cos()*zi.sinh()),
+        ComplexFn::Cos => (zr.cos()*zi.cosh(), -zr.sin()*zi.sinh()),
+        ComplexFn::Exp =>
+        {
+            let magnitude = zr.exp();
+
+            (magnitude*zi.cos(), magnitude*zi.sin())
+        },
+        ComplexFn::Conj => (zr, -zi)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token
+{
+    Number(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String>
+{
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&ch) = chars.peek()
+    {
+        match ch
+        {
+            ' ' | '\t' => { chars.next(); },
+            '+' => { chars.next(); tokens.push(Token::Plus); },
+            '-' => { chars.next(); tokens.push(Token::Minus); },
+            '*' => { chars.next(); tokens.push(Token::Star); },
+            '/' => { chars.next(); tokens.push(Token::Slash); },
+            '^' => { chars.next(); tokens.push(Token::Caret); },
+            '(' => { chars.next(); tokens.push(Token::LParen); },
+            ')' => { chars.next(); tokens.push(Token::RParen); },
+            c if c.is_ascii_digit() || c == '.' =>
+            {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek()
+                {
+                    if c.is_ascii_digit() || c == '.'
+                    {
+                        number.push(c);
+                        chars.next();
+                    } else
+                    {
+                        break;
+                    }
+                }
+
+                let value = number.parse().map_err(|err| format!("cant parse number {number}: {err}"))?;
+                tokens.push(Token::Number(value));
+            },
+            c if c.is_alphabetic() =>
+            {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek()
+                {
+                    if c.is_alphanumeric()
+                    {
+                        ident.push(c);
+                        chars.next();
+                    } else
+                    {
+                        break;
+                    }
+                }
+
+                tokens.push(Token::Ident(ident));
+            },
+            _ => return Err(format!("unexpected character in formula: {ch}"))
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser
+{
+    tokens: Vec<Token>,
+    pos: usize
+}
+
+impl Parser
+{
+    fn peek(&self) -> Option<&Token>
+    {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token>
+    {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+
+        token
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr, String>
+    {
+        let mut node = self.parse_term()?;
+
+        loop
+        {
+            match self.peek()
+            {
+                Some(Token::Plus) =>
+                {
+                    self.next();
+                    node = Expr::Add(Box::new(node), Box::new(self.parse_term()?));
+                },
+                Some(Token::Minus) =>
+                {
+                    self.next();
+                    node = Expr::Sub(Box::new(node), Box::new(self.parse_term()?));
+                },
+                _ => break
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, String>
+    {
+        let mut node = self.parse_factor()?;
+
+        loop
+        {
+            match self.peek()
+            {
+                Some(Token::Star) =>
+                {
+                    self.next();
+                    node = Expr::Mul(Box::new(node), Box::new(self.parse_factor()?));
+                },
+                Some(Token::Slash) =>
+                {
+                    self.next();
+                    node = Expr::Div(Box::new(node), Box::new(self.parse_factor()?));
+                },
+                _ => break
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn parse_factor(&mut self) -> Result<Expr, String>
+    {
+        if let Some(Token::Minus) = self.peek()
+        {
+            self.next();
+
+            Ok(Expr::Neg(Box::new(self.parse_factor()?)))
+        } else
+        {
+            self.parse_power()
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Expr, String>
+    {
+        let node = self.parse_primary()?;
+
+        if let Some(Token::Caret) = self.peek()
+        {
+            self.next();
+
+            match self.next()
+            {
+                Some(Token::Number(power)) => Ok(Expr::Pow(Box::new(node), power as i32)),
+                other => Err(format!("expected an integer power, got {other:?}"))
+            }
+        } else
+        {
+            Ok(node)
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String>
+    {
+        match self.next()
+        {
+            Some(Token::Number(value)) => Ok(Expr::Const(value)),
+            Some(Token::Ident(name)) => self.parse_ident(name),
+            Some(Token::LParen) =>
+            {
+                let node = self.parse_expr()?;
+
+                match self.next()
+                {
+                    Some(Token::RParen) => Ok(node),
+                    other => Err(format!("expected a closing paren, got {other:?}"))
+                }
+            },
+            other => Err(format!("unexpected token in formula: {other:?}"))
+        }
+    }
+
+    fn parse_ident(&mut self, name: String) -> Result<Expr, String>
+    {
+        if let Some(Token::LParen) = self.peek()
+        {
+            self.next();
+
+            let arg = self.parse_expr()?;
+
+            match self.next()
+            {
+                Some(Token::RParen) => {},
+                other => return Err(format!("expected a closing paren, got {other:?}"))
+            }
+
+            let func = match name.as_str()
+            {
+                "sin" => ComplexFn::Sin,
+                "cos" => ComplexFn::Cos,
+                "exp" => ComplexFn::Exp,
+                "conj" => ComplexFn::Conj,
+                _ => return Err(format!("unknown function: {name}"))
+            };
+
+            Ok(Expr::Call(func, Box::new(arg)))
+        } else
+        {
+            match name.as_str()
+            {
+                "z" => Ok(Expr::Z),
+                "c" => Ok(Expr::C),
+                _ => Err(format!("unknown identifier: {name}"))
+            }
+        }
+    }
+}
+
+fn parse_formula(input: &str) -> Result<Expr, String>
+{
+    let tokens = tokenize(input)?;
+    let tokens_len = tokens.len();
+
+    let mut parser = Parser{tokens, pos: 0};
+    let expr = parser.parse_expr()?;
+
+    if parser.pos != tokens_len
+    {
+        return Err("trailing tokens in formula".to_string());
+    }
+
+    Ok(expr)
+}
+
+fn lerp(c0: [u8; 3], c1: [u8; 3], amount: f64, colorspace: ColorSpace) -> [u8; 3]
+{
+    match colorspace
+    {
+        ColorSpace::Rgb => lerp_rgb(c0, c1, amount),
+        ColorSpace::Lab => lerp_space(c0, c1, amount, rgb_to_lab, lab_to_rgb),
+        ColorSpace::Luv => lerp_space(c0, c1, amount, rgb_to_luv, luv_to_rgb)
+    }
+}
+
+fn lerp_rgb(c0: [u8; 3], c1: [u8; 3], amount: f64) -> [u8; 3]
+{
+    let v_lerp = |n: usize|
     {
         (c0[n] as i16 + ((c1[n] as i16 - c0[n] as i16) as f64 * amount) as i16) as u8
     };
@@ -193,6 +767,193 @@ fn lerp(c0: [u8; 3], c1: [u8; 3], amount: f64) -> [u8; 3]
     [r, g, b]
 }
 
+fn lerp_space(
+    c0: [u8; 3],
+    c1: [u8; 3],
+    amount: f64,
+    to_space: impl Fn([u8; 3]) -> (f64, f64, f64),
+    from_space: impl Fn((f64, f64, f64)) -> [u8; 3]
+) -> [u8; 3]
+{
+    let (l0, a0, b0) = to_space(c0);
+    let (l1, a1, b1) = to_space(c1);
+
+    let l = l0 + (l1-l0)*amount;
+    let a = a0 + (a1-a0)*amount;
+    let b = b0 + (b1-b0)*amount;
+
+    from_space((l, a, b))
+}
+
+const WHITE_X: f64 = 95.047;
+const WHITE_Y: f64 = 100.0;
+const WHITE_Z: f64 = 108.883;
+
+fn srgb_to_linear(c: f64) -> f64
+{
+    if c <= 0.04045
+    {
+        c/12.92
+    } else
+    {
+        ((c+0.055)/1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64
+{
+    if c <= 0.0031308
+    {
+        c*12.92
+    } else
+    {
+        1.055*c.powf(1.0/2.4) - 0.055
+    }
+}
+
+fn rgb_to_xyz(c: [u8; 3]) -> (f64, f64, f64)
+{
+    let r = srgb_to_linear(c[0] as f64/255.0);
+    let g = srgb_to_linear(c[1] as f64/255.0);
+    let b = srgb_to_linear(c[2] as f64/255.0);
+
+    let x = r*41.24 + g*35.76 + b*18.05;
+    let y = r*21.26 + g*71.52 + b*7.22;
+    let z = r*1.93 + g*11.92 + b*95.05;
+
+    (x, y, z)
+}
+
+fn xyz_to_rgb(xyz: (f64, f64, f64)) -> [u8; 3]
+{
+    let (x, y, z) = (xyz.0/100.0, xyz.1/100.0, xyz.2/100.0);
+
+    let r = x*3.2406 + y*(-1.5372) + z*(-0.4986);
+    let g = x*(-0.9689) + y*1.8758 + z*0.0415;
+    let b = x*0.0557 + y*(-0.2040) + z*1.0570;
+
+    let to_byte = |c: f64| (linear_to_srgb(c).clamp(0.0, 1.0)*255.0).round() as u8;
+
+    [to_byte(r), to_byte(g), to_byte(b)]
+}
+
+fn lab_f(t: f64) -> f64
+{
+    if t > 0.008856
+    {
+        t.powf(1.0/3.0)
+    } else
+    {
+        7.787*t + 16.0/116.0
+    }
+}
+
+fn lab_f_inv(t: f64) -> f64
+{
+    if t.powi(3) > 0.008856
+    {
+        t.powi(3)
+    } else
+    {
+        (t - 16.0/116.0)/7.787
+    }
+}
+
+fn rgb_to_lab(c: [u8; 3]) -> (f64, f64, f64)
+{
+    let (x, y, z) = rgb_to_xyz(c);
+
+    let fx = lab_f(x/WHITE_X);
+    let fy = lab_f(y/WHITE_Y);
+    let fz = lab_f(z/WHITE_Z);
+
+    let l = 116.0*fy - 16.0;
+    let a = 500.0*(fx - fy);
+    let b = 200.0*(fy - fz);
+
+    (l, a, b)
+}
+
+fn lab_to_rgb(lab: (f64, f64, f64)) -> [u8; 3]
+{
+    let (l, a, b) = lab;
+
+    let fy = (l + 16.0)/116.0;
+    let fx = fy + a/500.0;
+    let fz = fy - b/200.0;
+
+    let x = WHITE_X*lab_f_inv(fx);
+    let y = WHITE_Y*lab_f_inv(fy);
+    let z = WHITE_Z*lab_f_inv(fz);
+
+    xyz_to_rgb((x, y, z))
+}
+
+fn luv_white_uv() -> (f64, f64)
+{
+    let denom = WHITE_X + 15.0*WHITE_Y + 3.0*WHITE_Z;
+
+    (4.0*WHITE_X/denom, 9.0*WHITE_Y/denom)
+}
+
+fn rgb_to_luv(c: [u8; 3]) -> (f64, f64, f64)
+{
+    let (x, y, z) = rgb_to_xyz(c);
+
+    let denom = x + 15.0*y + 3.0*z;
+    let (u_prime, v_prime) = if denom == 0.0
+    {
+        (0.0, 0.0)
+    } else
+    {
+        (4.0*x/denom, 9.0*y/denom)
+    };
+
+    let (un, vn) = luv_white_uv();
+
+    let yr = y/WHITE_Y;
+    let l = if yr > 0.008856
+    {
+        116.0*yr.powf(1.0/3.0) - 16.0
+    } else
+    {
+        903.3*yr
+    };
+
+    let u = 13.0*l*(u_prime - un);
+    let v = 13.0*l*(v_prime - vn);
+
+    (l, u, v)
+}
+
+fn luv_to_rgb(luv: (f64, f64, f64)) -> [u8; 3]
+{
+    let (l, u, v) = luv;
+
+    if l <= 0.0
+    {
+        return xyz_to_rgb((0.0, 0.0, 0.0));
+    }
+
+    let (un, vn) = luv_white_uv();
+
+    let u_prime = u/(13.0*l) + un;
+    let v_prime = v/(13.0*l) + vn;
+
+    let y = if l > 8.0
+    {
+        WHITE_Y*((l + 16.0)/116.0).powi(3)
+    } else
+    {
+        WHITE_Y*l/903.3
+    };
+
+    let x = y*9.0*u_prime/(4.0*v_prime);
+    let z = y*(12.0 - 3.0*u_prime - 20.0*v_prime)/(4.0*v_prime);
+
+    xyz_to_rgb((x, y, z))
+}
+
 fn parse_color(arg: Option<String>) -> Result<[u8; 3], String>
 {
     let arg = arg.ok_or("no argument supplied")?;
@@ -211,6 +972,26 @@ fn parse_color(arg: Option<String>) -> Result<[u8; 3], String>
     Ok(out)
 }
 
+fn parse_pair(arg: Option<String>) -> Result<(f64, f64), String>
+{
+    let arg = arg.ok_or("no argument supplied")?;
+
+    let mut values = arg.split(',');
+
+    let a = values.next().ok_or("not enough values")?;
+    let b = values.next().ok_or("not enough values")?;
+
+    if values.next().is_some()
+    {
+        return Err("too many values".to_string());
+    }
+
+    let a: f64 = a.trim().parse().map_err(|err| format!("cant parse {a}: {err}"))?;
+    let b: f64 = b.trim().parse().map_err(|err| format!("cant parse {b}: {err}"))?;
+
+    Ok((a, b))
+}
+
 fn parse_arg<T>(arg: Option<String>) -> Result<T, String>
 where
     T: FromStr,